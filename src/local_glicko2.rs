@@ -1,71 +1,219 @@
 // BEGIN MODIFIED CODE FROM https://crates.io/crates/skillratings
 
-pub fn glicko2(
-    player_one: &skillratings::glicko2::Glicko2Rating,
-    player_two: &skillratings::glicko2::Glicko2Rating,
-    outcome: &f64,
+/// Computes a single player's Glicko-2 update from every game they played in
+/// one rating period, per Glickman's canonical batch formulation. Passing a
+/// single-opponent slice also covers the sequential (non rating-period)
+/// mode, folding in one opponent at a time.
+///
+/// Passing an empty `results` slice represents a player who sat out the
+/// period entirely: per the spec, their rating and volatility are left
+/// untouched, but their deviation still grows to reflect the added
+/// uncertainty.
+///
+/// # Arguments
+///
+/// * `player` - The player's rating going into this period.
+/// * `results` - Every `(opponent_rating, outcome, advantage)` from this
+///   period, where `advantage` is the net Glicko-Boost-style side/color
+///   advantage (on the 1500 scale) in that particular game: positive if
+///   `player` held it, negative if `opponent` held it, or `0.0` for none.
+/// * `config` - The Glicko-2 configuration to be used in rating calculation.
+pub fn glicko2_period(
+    player: &skillratings::glicko2::Glicko2Rating,
+    results: &[(skillratings::glicko2::Glicko2Rating, f64, f64)],
     config: &skillratings::glicko2::Glicko2Config,
-) -> (
-    skillratings::glicko2::Glicko2Rating,
-    skillratings::glicko2::Glicko2Rating,
-) {
-    // First we need to convert the ratings into the glicko-2 scale.
-    let player_one_rating = (player_one.rating - 1500.0) / 173.7178;
-    let player_two_rating = (player_two.rating - 1500.0) / 173.7178;
-
-    // Same with the deviation.
-    let player_one_deviation = player_one.deviation / 173.7178;
-    let player_two_deviation = player_two.deviation / 173.7178;
-
-    let outcome1 = outcome.clone();
-    let outcome2 = 1.0 - outcome1;
-
-    // We always need the deviation of the opponent in the g function.
-    let g1 = g_value(player_two_deviation);
-    let g2 = g_value(player_one_deviation);
-
-    let e1 = e_value(player_one_rating, player_two_rating, g1);
-    let e2 = e_value(player_two_rating, player_one_rating, g2);
-
-    let v1 = v_value(g1, e1);
-    let v2 = v_value(g2, e2);
-
-    let player_one_new_volatility = new_volatility(
-        player_one.volatility,
-        delta_value(outcome1, v1, g1, e1).powi(2),
-        player_one_deviation.powi(2),
-        v1,
-        config.tau,
-        config.convergence_tolerance,
-    );
-    let player_two_new_volatility = new_volatility(
-        player_two.volatility,
-        delta_value(outcome2, v2, g2, e2).powi(2),
-        player_two_deviation.powi(2),
-        v2,
+) -> skillratings::glicko2::Glicko2Rating {
+    // First we need to convert the rating into the glicko-2 scale.
+    let player_rating = (player.rating - 1500.0) / 173.7178;
+    let player_deviation = player.deviation / 173.7178;
+
+    if results.is_empty() {
+        let pre_deviation = player_deviation.hypot(player.volatility);
+
+        return skillratings::glicko2::Glicko2Rating {
+            rating: player.rating,
+            deviation: pre_deviation * 173.7178,
+            volatility: player.volatility,
+        };
+    }
+
+    // v and the aggregate rating change are sums over every opponent j faced
+    // this period.
+    let mut v_reciprocal = 0.0;
+    let mut g_e_sum = 0.0;
+
+    for (opponent, outcome, advantage) in results {
+        let opponent_rating = (opponent.rating - 1500.0) / 173.7178;
+        let opponent_deviation = opponent.deviation / 173.7178;
+
+        // The advantage only biases this game's expectation; the rating it
+        // folds into remains the player's true (unshifted) rating.
+        let advantaged_player_rating = player_rating + (advantage / 173.7178);
+
+        let g = g_value(opponent_deviation);
+        let e = e_value(advantaged_player_rating, opponent_rating, g);
+
+        v_reciprocal += g.powi(2) * e * (1.0 - e);
+        g_e_sum += g * (outcome - e);
+    }
+
+    let v = v_reciprocal.recip();
+    let delta = v * g_e_sum;
+
+    let new_volatility = new_volatility(
+        player.volatility,
+        delta.powi(2),
+        player_deviation.powi(2),
+        v,
         config.tau,
         config.convergence_tolerance,
     );
 
-    let new_deviation1 = new_deviation(player_one_deviation, player_one_new_volatility, v1);
-    let new_deviation2 = new_deviation(player_two_deviation, player_two_new_volatility, v2);
+    let pre_deviation = player_deviation.hypot(new_volatility);
+    let new_deviation = ((pre_deviation.powi(2).recip()) + v.recip())
+        .sqrt()
+        .recip();
+    let new_rating = new_deviation.powi(2).mul_add(g_e_sum, player_rating);
 
-    let new_rating1 = new_rating(player_one_rating, new_deviation1, outcome1, g1, e1);
-    let new_rating2 = new_rating(player_two_rating, new_deviation2, outcome2, g2, e2);
+    skillratings::glicko2::Glicko2Rating {
+        rating: new_rating.mul_add(173.7178, 1500.0),
+        deviation: new_deviation * 173.7178,
+        volatility: new_volatility,
+    }
+}
 
-    // We return the new values, converted back to the original scale.
-    let player_one_new = skillratings::glicko2::Glicko2Rating {
-        rating: new_rating1.mul_add(173.7178, 1500.0),
-        deviation: new_deviation1 * 173.7178,
-        volatility: player_one_new_volatility,
-    };
-    let player_two_new = skillratings::glicko2::Glicko2Rating {
-        rating: new_rating2.mul_add(173.7178, 1500.0),
-        deviation: new_deviation2 * 173.7178,
-        volatility: player_two_new_volatility,
-    };
+/// Computes a single player's Glicko-1 update from every game they played in
+/// one rating period. Shares `glicko2_period`'s g/E functions, but has no
+/// volatility iteration: the deviation update is the direct `RD' =
+/// 1/sqrt(1/RD^2 + 1/v)`.
+///
+/// Passing an empty `results` slice leaves the player untouched; Glicko-1
+/// has no systematic constant of its own to grow an idle player's deviation
+/// with (`decay_deviation` covers that, if requested).
+///
+/// # Arguments
+///
+/// * `player` - The player's rating going into this period.
+/// * `results` - Every `(opponent_rating, outcome, advantage)` from this
+///   period, where `advantage` is the net advantage (positive if `player`
+///   held it, negative if `opponent` held it, `0.0` for none) as in
+///   [`glicko2_period`].
+pub fn glicko1_period(
+    player: &skillratings::glicko2::Glicko2Rating,
+    results: &[(skillratings::glicko2::Glicko2Rating, f64, f64)],
+) -> skillratings::glicko2::Glicko2Rating {
+    if results.is_empty() {
+        return *player;
+    }
+
+    let player_rating = (player.rating - 1500.0) / 173.7178;
+    let player_deviation = player.deviation / 173.7178;
+
+    let mut v_reciprocal = 0.0;
+    let mut g_e_sum = 0.0;
+
+    for (opponent, outcome, advantage) in results {
+        let opponent_rating = (opponent.rating - 1500.0) / 173.7178;
+        let opponent_deviation = opponent.deviation / 173.7178;
+
+        let advantaged_player_rating = player_rating + (advantage / 173.7178);
+
+        let g = g_value(opponent_deviation);
+        let e = e_value(advantaged_player_rating, opponent_rating, g);
+
+        v_reciprocal += g.powi(2) * e * (1.0 - e);
+        g_e_sum += g * (outcome - e);
+    }
+
+    let v = v_reciprocal.recip();
+
+    let new_deviation = ((player_deviation.powi(2).recip()) + v.recip())
+        .sqrt()
+        .recip();
+    let new_rating = new_deviation.powi(2).mul_add(g_e_sum, player_rating);
+
+    skillratings::glicko2::Glicko2Rating {
+        rating: new_rating.mul_add(173.7178, 1500.0),
+        deviation: new_deviation * 173.7178,
+        volatility: player.volatility,
+    }
+}
+
+/// Computes a single player's Elo update from every game they played in one
+/// rating period (or a single game, in sequential mode), applied in order so
+/// that later games in the period see the rating changes from earlier ones.
+///
+/// # Arguments
+///
+/// * `player` - The player's rating going into this period.
+/// * `results` - Every `(opponent_rating, outcome, advantage)` from this
+///   period, where `advantage` is the net advantage (positive if `player`
+///   held it, negative if `opponent` held it, `0.0` for none) as in
+///   [`glicko2_period`].
+/// * `k_factor` - The K-factor controlling how much each game moves the rating.
+pub fn elo_update(
+    player: &skillratings::glicko2::Glicko2Rating,
+    results: &[(skillratings::glicko2::Glicko2Rating, f64, f64)],
+    k_factor: f64,
+) -> skillratings::glicko2::Glicko2Rating {
+    let mut rating = player.rating;
+
+    for (opponent, outcome, advantage) in results {
+        let e = (1.0 + 10f64.powf(-((rating + advantage - opponent.rating) / 400.0))).recip();
+        rating += k_factor * (outcome - e);
+    }
+
+    skillratings::glicko2::Glicko2Rating { rating, ..*player }
+}
+
+/// Inflates a rating deviation for a player who sat out a rating period, per
+/// the standard Glicko pre-period deviation step: `RD = min(sqrt(RD^2 +
+/// C^2), RD_max)`. Called once per idle period, so that a player skipping
+/// several periods in a row accumulates the inflation incrementally.
+///
+/// # Arguments
+///
+/// * `deviation` - The player's rating deviation before this idle period.
+/// * `decay_constant` - The system constant `C` controlling how fast idle deviation grows.
+/// * `max_deviation` - The ceiling `RD_max` the deviation is not allowed to exceed.
+pub fn decay_deviation(deviation: f64, decay_constant: f64, max_deviation: f64) -> f64 {
+    deviation.hypot(decay_constant).min(max_deviation)
+}
+
+/// Computes the expected score (win probability) of `player_a` against
+/// `player_b`, without updating either rating.
+///
+/// # Arguments
+///
+/// * `player_a` - The rating of the player whose win probability is computed.
+/// * `player_b` - The rating of the opponent.
+pub fn predict(
+    player_a: &skillratings::glicko2::Glicko2Rating,
+    player_b: &skillratings::glicko2::Glicko2Rating,
+) -> f64 {
+    let player_a_rating = (player_a.rating - 1500.0) / 173.7178;
+    let player_b_rating = (player_b.rating - 1500.0) / 173.7178;
+
+    let player_a_deviation = player_a.deviation / 173.7178;
+    let player_b_deviation = player_b.deviation / 173.7178;
+
+    let g = g_value(player_a_deviation.hypot(player_b_deviation));
 
-    (player_one_new, player_two_new)
+    e_value(player_a_rating, player_b_rating, g)
+}
+
+/// Computes the Elo expected score (win probability) of `player_a` against
+/// `player_b`, without updating either rating.
+///
+/// # Arguments
+///
+/// * `player_a` - The rating of the player whose win probability is computed.
+/// * `player_b` - The rating of the opponent.
+pub fn predict_elo(
+    player_a: &skillratings::glicko2::Glicko2Rating,
+    player_b: &skillratings::glicko2::Glicko2Rating,
+) -> f64 {
+    (1.0 + 10f64.powf(-((player_a.rating - player_b.rating) / 400.0))).recip()
 }
 
 fn g_value(deviation: f64) -> f64 {
@@ -78,14 +226,6 @@ fn e_value(rating: f64, opponent_rating: f64, g: f64) -> f64 {
     (1.0 + (-g * (rating - opponent_rating)).exp()).recip()
 }
 
-fn v_value(g: f64, e: f64) -> f64 {
-    (g.powi(2) * e * (1.0 - e)).recip()
-}
-
-fn delta_value(outcome: f64, v: f64, g: f64, e: f64) -> f64 {
-    v * (g * (outcome - e))
-}
-
 fn f_value(
     x: f64,
     delta_square: f64,
@@ -151,16 +291,4 @@ fn new_volatility(
     (a / 2.0).exp()
 }
 
-fn new_deviation(deviation: f64, new_volatility: f64, v: f64) -> f64 {
-    let pre_deviation = deviation.hypot(new_volatility);
-
-    ((pre_deviation.powi(2).recip()) + (v.recip()))
-        .sqrt()
-        .recip()
-}
-
-fn new_rating(rating: f64, new_deviation: f64, outcome: f64, g_value: f64, e_value: f64) -> f64 {
-    (new_deviation.powi(2) * g_value).mul_add(outcome - e_value, rating)
-}
-
 // END MODIFIED CODE FROM https://crates.io/crates/skillratings
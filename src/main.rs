@@ -4,6 +4,17 @@ use std::error::Error;
 
 mod local_glicko2;
 
+/// A rating algorithm csvglicko can run the CSV pipeline through.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Algorithm {
+    /// Full Glicko-2, with volatility.
+    Glicko2,
+    /// Glicko-1: the same g/E functions, but no volatility iteration.
+    Glicko,
+    /// Elo, with a configurable K-factor and no deviation/volatility.
+    Elo,
+}
+
 /// Command-line arguments for csvglicko.
 #[derive(Parser)]
 #[command(about)]
@@ -116,6 +127,61 @@ struct Args {
         help = "Disable invisible indexes when filtering. (i.e. No gaps in printed index)"
     )]
     invisible_indexes: bool,
+
+    /// Process games by rating period instead of sequentially.
+    #[arg(
+        long = "rating-period",
+        help = "Process games by rating period (4th CSV column) instead of sequentially, per the canonical Glicko-2 batch update."
+    )]
+    rating_period: bool,
+
+    /// Print the expected score of PLAYER_A against PLAYER_B instead of rating.
+    #[arg(
+        long = "predict",
+        num_args = 2,
+        value_names = ["PLAYER_A", "PLAYER_B"],
+        help = "Print the expected score of PLAYER_A against PLAYER_B, using ratings built from the history file, instead of rating it."
+    )]
+    predict: Option<Vec<String>>,
+
+    /// Batch form of --predict: read matchup pairs from a second CSV file.
+    #[arg(
+        long = "predict-csv",
+        help = "Read matchup pairs (two columns of player names) from a CSV file and print the expected score of each, instead of rating the history file."
+    )]
+    predict_csv: Option<String>,
+
+    /// Glicko-Boost-style side/color advantage, for asymmetric games.
+    #[arg(
+        long = "advantage",
+        help = "Side/color advantage (e.g. White in chess) added to the expectation of whichever player the CSV's side column marks as advantaged. Default of 0 applies no advantage.",
+        default_value = "0.0"
+    )]
+    advantage: f64,
+
+    /// Decay constant controlling how fast idle players' deviation grows in --rating-period mode.
+    #[arg(
+        long = "decay-constant",
+        requires = "rating_period",
+        help = "In --rating-period mode, inflate idle players' deviation each skipped period as min(sqrt(RD^2 + C^2), default-deviation) instead of via volatility."
+    )]
+    decay_constant: Option<f64>,
+
+    /// Rating algorithm to use.
+    #[arg(
+        long = "algorithm",
+        help = "Rating algorithm to use.",
+        default_value = "glicko2"
+    )]
+    algorithm: Algorithm,
+
+    /// K-factor used by the Elo algorithm.
+    #[arg(
+        long = "k-factor",
+        help = "K-factor used by the Elo algorithm.",
+        default_value = "32.0"
+    )]
+    k_factor: f64,
 }
 
 /// A representation of one rated player.
@@ -144,7 +210,26 @@ fn main() {
     };
 
     // Generate all ratings from stdin
-    let ratings = match rate_file(&glicko2_config, &glicko2_default_rating, &args.csv) {
+    let ratings = match if args.rating_period {
+        rate_file_periods(
+            &glicko2_config,
+            &glicko2_default_rating,
+            &args.csv,
+            args.advantage,
+            args.decay_constant,
+            args.algorithm,
+            args.k_factor,
+        )
+    } else {
+        rate_file(
+            &glicko2_config,
+            &glicko2_default_rating,
+            &args.csv,
+            args.advantage,
+            args.algorithm,
+            args.k_factor,
+        )
+    } {
         Ok(ratings) => ratings,
         Err(e) => {
             println!(
@@ -154,10 +239,53 @@ fn main() {
             return;
         }
     };
+    // Prediction modes print expected scores and skip the rating output below.
+    if let Some(pair) = &args.predict {
+        print_prediction(
+            &ratings,
+            &glicko2_default_rating,
+            args.algorithm,
+            &pair[0],
+            &pair[1],
+        );
+        return;
+    }
+
+    if let Some(predict_csv) = &args.predict_csv {
+        if let Err(e) = predict_file(
+            &ratings,
+            &glicko2_default_rating,
+            args.algorithm,
+            predict_csv,
+        ) {
+            println!(
+                "There was a problem opening or reading the file \"{}\": {}",
+                predict_csv, e
+            );
+        }
+        return;
+    }
+
+    // Elo has no notion of deviation or volatility, so options that filter or
+    // sort by either would otherwise silently act on a frozen, meaningless
+    // field.
+    let is_elo = matches!(args.algorithm, Algorithm::Elo);
+    if is_elo
+        && (args.maximum_deviation.is_some()
+            || args.minimum_deviation.is_some()
+            || args.filter_provisional
+            || args.sort_rating_deviation
+            || args.sort_volatility)
+    {
+        eprintln!(
+            "Warning: --maximum-deviation, --minimum-deviation, --filter-provisional, --sort-deviation, and --sort-volatility have no effect under --algorithm elo, since Elo has no notion of deviation or volatility."
+        );
+    }
+
     let mut ratings_sorted: Vec<_> = ratings.into_iter().collect();
 
     // Sort ratings according to options.
-    if args.sort_rating_deviation {
+    if args.sort_rating_deviation && !is_elo {
         if !args.sort_reverse {
             ratings_sorted.sort_by(|a, b| {
                 a.1.rating
@@ -173,7 +301,7 @@ fn main() {
                     .unwrap()
             });
         }
-    } else if args.sort_volatility {
+    } else if args.sort_volatility && !is_elo {
         if !args.sort_reverse {
             ratings_sorted.sort_by(|a, b| {
                 b.1.rating
@@ -209,7 +337,8 @@ fn main() {
         }
 
         // If the maximum deviation option is set, limit all output to below that number
-        if args.maximum_deviation.is_some()
+        if !is_elo
+            && args.maximum_deviation.is_some()
             && player.1.rating.deviation > args.maximum_deviation.unwrap() as f64
         {
             if args.invisible_indexes {
@@ -220,7 +349,8 @@ fn main() {
         }
 
         // If the minimum deviation option is set, limit all output to above that number
-        if args.minimum_deviation.is_some()
+        if !is_elo
+            && args.minimum_deviation.is_some()
             && player.1.rating.deviation < args.minimum_deviation.unwrap() as f64
         {
             if args.invisible_indexes {
@@ -230,7 +360,10 @@ fn main() {
         }
 
         // Filter out provisional ratings if the filter_provisional flag is set
-        if args.filter_provisional && player.1.rating.deviation > args.provisional_threshold {
+        if !is_elo
+            && args.filter_provisional
+            && player.1.rating.deviation > args.provisional_threshold
+        {
             if args.invisible_indexes {
                 index_subtraction += 1;
             }
@@ -239,21 +372,49 @@ fn main() {
 
         // Determine whether the provisional mark should be empty or a question mark
         let mut provisional_mark: &str = " ";
-        if player.1.rating.deviation > args.provisional_threshold {
+        if !is_elo && player.1.rating.deviation > args.provisional_threshold {
             provisional_mark = "?";
         }
 
-        println!(
-            "{:0index_width$}. {}{} ({}) {} {} {}",
-            (index as i32) + 1 - index_subtraction,
-            format!("{:07.2}", player.1.rating.rating).red(),
-            provisional_mark.yellow(),
-            format!("{:+07.2}", player.1.latest_change),
-            format!("{:03.0}", player.1.rating.deviation).cyan(),
-            format!("{:.8}", player.1.rating.volatility).purple(),
-            player.0.to_string().blue(),
-            index_width = ratings_sorted.len().to_string().len()
-        );
+        if is_elo {
+            println!(
+                "{:0index_width$}. {}{} ({}) {}",
+                (index as i32) + 1 - index_subtraction,
+                format!("{:07.2}", player.1.rating.rating).red(),
+                provisional_mark.yellow(),
+                format!("{:+07.2}", player.1.latest_change),
+                player.0.to_string().blue(),
+                index_width = ratings_sorted.len().to_string().len()
+            );
+        } else {
+            println!(
+                "{:0index_width$}. {}{} ({}) {} {} {}",
+                (index as i32) + 1 - index_subtraction,
+                format!("{:07.2}", player.1.rating.rating).red(),
+                provisional_mark.yellow(),
+                format!("{:+07.2}", player.1.latest_change),
+                format!("{:03.0}", player.1.rating.deviation).cyan(),
+                format!("{:.8}", player.1.rating.volatility).purple(),
+                player.0.to_string().blue(),
+                index_width = ratings_sorted.len().to_string().len()
+            );
+        }
+    }
+}
+
+/// Dispatches a single player's batch update to whichever rating algorithm
+/// was selected on the command line.
+fn update_player(
+    rating: &skillratings::glicko2::Glicko2Rating,
+    opponents: &[(skillratings::glicko2::Glicko2Rating, f64, f64)],
+    config: &skillratings::glicko2::Glicko2Config,
+    algorithm: Algorithm,
+    k_factor: f64,
+) -> skillratings::glicko2::Glicko2Rating {
+    match algorithm {
+        Algorithm::Glicko2 => local_glicko2::glicko2_period(rating, opponents, config),
+        Algorithm::Glicko => local_glicko2::glicko1_period(rating, opponents),
+        Algorithm::Elo => local_glicko2::elo_update(rating, opponents, k_factor),
     }
 }
 
@@ -263,23 +424,38 @@ fn main() {
 ///
 /// * `glicko2_config` - The Glicko-2 configuration to be used in rating calculation.
 /// * `glicko2_default_rating` - The default Glicko-2 rating to be used for newly-instantiated players.
+/// * `advantage` - The side/color advantage (1500 scale) to give whichever player the CSV's optional 4th column marks as advantaged ("1" or "2").
+/// * `algorithm` - The rating algorithm to rate games with.
+/// * `k_factor` - The K-factor to use if `algorithm` is [`Algorithm::Elo`].
 fn rate_file(
     glicko2_config: &skillratings::glicko2::Glicko2Config,
     glicko2_default_rating: &skillratings::glicko2::Glicko2Rating,
     file_path: &String,
+    advantage: f64,
+    algorithm: Algorithm,
+    k_factor: f64,
 ) -> Result<std::collections::HashMap<String, Player>, Box<dyn Error>> {
     let mut players: std::collections::HashMap<String, Player> = std::collections::HashMap::new();
 
     let file = std::fs::File::open(file_path)?;
 
-    let mut reader = csv::Reader::from_reader(file);
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .has_headers(false)
+        .from_reader(file);
     for result in reader.records() {
         // Unwrap the line
         let record = result?;
 
         // Get the player names from the csv line
-        let player_1_name = record.get(0).unwrap().to_string();
-        let player_2_name = record.get(1).unwrap().to_string();
+        let player_1_name = record
+            .get(0)
+            .ok_or("each row needs a 1st CSV column holding the first player's name")?
+            .to_string();
+        let player_2_name = record
+            .get(1)
+            .ok_or("each row needs a 2nd CSV column holding the second player's name")?
+            .to_string();
 
         // Skip game if a player is fighting themselves somehow
         if player_1_name == player_2_name {
@@ -287,7 +463,21 @@ fn rate_file(
         }
 
         // Get the outcome of the game from the csv line
-        let outcome: f64 = record.get(2).unwrap().parse().unwrap();
+        let outcome: f64 = record
+            .get(2)
+            .ok_or("each row needs a 3rd CSV column holding the game outcome")?
+            .parse()
+            .unwrap();
+
+        // Get the side (if any) the advantage applies to from the csv line.
+        // Each side's value is the *net* advantage it holds this game, so the
+        // disadvantaged side sees the other side's eta reflected as a
+        // negative shift rather than being left at zero.
+        let (player_1_advantage, player_2_advantage) = match record.get(3) {
+            Some("1") => (advantage, -advantage),
+            Some("2") => (-advantage, advantage),
+            _ => (0.0, 0.0),
+        };
 
         // Get players from storage, or create them otherwise
         let mut player_1_rating: skillratings::glicko2::Glicko2Rating =
@@ -302,11 +492,19 @@ fn rate_file(
         }
 
         // Rate the game
-        let (new_player_1_rating, new_player_2_rating) = local_glicko2::glicko2(
+        let new_player_1_rating = update_player(
             &player_1_rating,
+            &[(player_2_rating, outcome, player_1_advantage)],
+            glicko2_config,
+            algorithm,
+            k_factor,
+        );
+        let new_player_2_rating = update_player(
             &player_2_rating,
-            &outcome,
-            &glicko2_config,
+            &[(player_1_rating, 1.0 - outcome, player_2_advantage)],
+            glicko2_config,
+            algorithm,
+            k_factor,
         );
 
         let player_1_rating_change = new_player_1_rating.rating - player_1_rating.rating;
@@ -331,3 +529,232 @@ fn rate_file(
 
     Ok(players)
 }
+
+/// Print the expected score of `player_a_name` against `player_b_name`.
+/// Players not present in `ratings` are assumed to hold `default_rating`.
+fn print_prediction(
+    ratings: &std::collections::HashMap<String, Player>,
+    default_rating: &skillratings::glicko2::Glicko2Rating,
+    algorithm: Algorithm,
+    player_a_name: &str,
+    player_b_name: &str,
+) {
+    let player_a_rating = ratings
+        .get(player_a_name)
+        .map_or(*default_rating, |p| p.rating);
+    let player_b_rating = ratings
+        .get(player_b_name)
+        .map_or(*default_rating, |p| p.rating);
+
+    let expected_score = if matches!(algorithm, Algorithm::Elo) {
+        local_glicko2::predict_elo(&player_a_rating, &player_b_rating)
+    } else {
+        local_glicko2::predict(&player_a_rating, &player_b_rating)
+    };
+
+    println!(
+        "{} vs {}: {}",
+        player_a_name.blue(),
+        player_b_name.blue(),
+        format!("{:.4}", expected_score).green()
+    );
+}
+
+/// Print the expected score for every matchup in a CSV file of
+/// `player_a,player_b` pairs.
+fn predict_file(
+    ratings: &std::collections::HashMap<String, Player>,
+    default_rating: &skillratings::glicko2::Glicko2Rating,
+    algorithm: Algorithm,
+    file_path: &str,
+) -> Result<(), Box<dyn Error>> {
+    let file = std::fs::File::open(file_path)?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(file);
+    for result in reader.records() {
+        let record = result?;
+
+        let player_a_name = record.get(0).unwrap();
+        let player_b_name = record.get(1).unwrap();
+
+        print_prediction(ratings, default_rating, algorithm, player_a_name, player_b_name);
+    }
+
+    Ok(())
+}
+
+/// Generate ratings for all players in the csv file at `file_path`,
+/// processing games one rating period at a time (keyed by the 4th CSV
+/// column) rather than sequentially, per Glickman's canonical Glicko-2 batch
+/// update. Under Glicko-2, or whenever `decay_constant` is set, a
+/// previously-seen player with no games in a period is still updated: their
+/// deviation grows to reflect the added uncertainty even though their
+/// rating doesn't move. Under Elo, and under Glicko-1 without a decay
+/// constant, idle players are left unchanged.
+///
+/// # Arguments
+///
+/// * `glicko2_config` - The Glicko-2 configuration to be used in rating calculation.
+/// * `glicko2_default_rating` - The default Glicko-2 rating to be used for newly-instantiated players.
+/// * `advantage` - The side/color advantage (1500 scale) to give whichever player the CSV's optional 5th column marks as advantaged ("1" or "2").
+/// * `decay_constant` - If set, idle players' deviation is inflated each skipped period as `min(sqrt(RD^2 + C^2), default deviation)` instead of via volatility.
+/// * `algorithm` - The rating algorithm to rate games with.
+/// * `k_factor` - The K-factor to use if `algorithm` is [`Algorithm::Elo`].
+fn rate_file_periods(
+    glicko2_config: &skillratings::glicko2::Glicko2Config,
+    glicko2_default_rating: &skillratings::glicko2::Glicko2Rating,
+    file_path: &String,
+    advantage: f64,
+    decay_constant: Option<f64>,
+    algorithm: Algorithm,
+    k_factor: f64,
+) -> Result<std::collections::HashMap<String, Player>, Box<dyn Error>> {
+    let mut players: std::collections::HashMap<String, Player> = std::collections::HashMap::new();
+
+    let file = std::fs::File::open(file_path)?;
+
+    // Bucket every game into its rating period, preserving the order periods
+    // first appear in the file.
+    let mut period_order: Vec<String> = Vec::new();
+    let mut periods: std::collections::HashMap<String, Vec<(String, String, f64, String)>> =
+        std::collections::HashMap::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .has_headers(false)
+        .from_reader(file);
+    for result in reader.records() {
+        let record = result?;
+
+        let player_1_name = record
+            .get(0)
+            .ok_or("each row needs a 1st CSV column holding the first player's name")?
+            .to_string();
+        let player_2_name = record
+            .get(1)
+            .ok_or("each row needs a 2nd CSV column holding the second player's name")?
+            .to_string();
+
+        // Skip game if a player is fighting themselves somehow
+        if player_1_name == player_2_name {
+            continue;
+        }
+
+        let outcome: f64 = record
+            .get(2)
+            .ok_or("each row needs a 3rd CSV column holding the game outcome")?
+            .parse()
+            .unwrap();
+        let period = record
+            .get(3)
+            .ok_or("--rating-period requires a 4th CSV column holding the period identifier")?
+            .to_string();
+        let side = record.get(4).unwrap_or("").to_string();
+
+        if !periods.contains_key(&period) {
+            period_order.push(period.clone());
+        }
+        periods
+            .entry(period)
+            .or_default()
+            .push((player_1_name, player_2_name, outcome, side));
+    }
+
+    for period in period_order {
+        let games = &periods[&period];
+
+        // Snapshot each involved player's pre-period rating, and collect
+        // every opponent they faced this period.
+        let mut period_games: std::collections::HashMap<
+            String,
+            Vec<(skillratings::glicko2::Glicko2Rating, f64, f64)>,
+        > = std::collections::HashMap::new();
+
+        for (player_1_name, player_2_name, outcome, side) in games {
+            let player_1_rating = players
+                .get(player_1_name)
+                .map_or(*glicko2_default_rating, |p| p.rating);
+            let player_2_rating = players
+                .get(player_2_name)
+                .map_or(*glicko2_default_rating, |p| p.rating);
+
+            // Each side's value is the *net* advantage it holds this game, so
+            // the disadvantaged side sees the other side's eta reflected as a
+            // negative shift rather than being left at zero.
+            let (player_1_advantage, player_2_advantage) = match side.as_str() {
+                "1" => (advantage, -advantage),
+                "2" => (-advantage, advantage),
+                _ => (0.0, 0.0),
+            };
+
+            period_games
+                .entry(player_1_name.clone())
+                .or_default()
+                .push((player_2_rating, *outcome, player_1_advantage));
+            period_games
+                .entry(player_2_name.clone())
+                .or_default()
+                .push((player_1_rating, 1.0 - outcome, player_2_advantage));
+        }
+
+        // Update every player who played this period.
+        for (name, opponents) in &period_games {
+            let rating = players
+                .get(name)
+                .map_or(*glicko2_default_rating, |p| p.rating);
+            let new_rating = update_player(&rating, opponents, glicko2_config, algorithm, k_factor);
+            let change = new_rating.rating - rating.rating;
+
+            players.insert(
+                name.clone(),
+                Player {
+                    rating: new_rating,
+                    latest_change: change,
+                },
+            );
+        }
+
+        // Idle players are still put through whichever deviation-growth step
+        // below applies to them, even though they didn't play.
+        let idle_names: Vec<String> = players
+            .keys()
+            .filter(|name| !period_games.contains_key(*name))
+            .cloned()
+            .collect();
+
+        for name in idle_names {
+            let rating = players.get(&name).unwrap().rating;
+            let new_rating = if matches!(algorithm, Algorithm::Elo) {
+                // Elo has no notion of deviation, so idle players are unaffected.
+                rating
+            } else if let Some(decay_constant) = decay_constant {
+                skillratings::glicko2::Glicko2Rating {
+                    rating: rating.rating,
+                    deviation: local_glicko2::decay_deviation(
+                        rating.deviation,
+                        decay_constant,
+                        glicko2_default_rating.deviation,
+                    ),
+                    volatility: rating.volatility,
+                }
+            } else if matches!(algorithm, Algorithm::Glicko2) {
+                local_glicko2::glicko2_period(&rating, &[], glicko2_config)
+            } else {
+                // Glicko-1 has no systemic deviation growth without an explicit decay constant.
+                rating
+            };
+
+            players.insert(
+                name,
+                Player {
+                    rating: new_rating,
+                    latest_change: 0.0,
+                },
+            );
+        }
+    }
+
+    Ok(players)
+}